@@ -1,18 +1,36 @@
 #[derive(Debug)]
 pub enum Error {
     Internal(String),
+    Io(std::io::Error),
+    Parquet(arrow2::error::Error),
 }
 
+pub type Result<T> = std::result::Result<T, Error>;
+
 impl std::convert::From<rusqlite::Error> for Error {
     fn from(err: rusqlite::Error) -> Self {
         Error::Internal(err.to_string())
     }
 }
 
+impl std::convert::From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl std::convert::From<arrow2::error::Error> for Error {
+    fn from(err: arrow2::error::Error) -> Self {
+        Error::Parquet(err)
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Error::Internal(message) => write!(f, "{}", message),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parquet(err) => write!(f, "{}", err),
         }
     }
 }
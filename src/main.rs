@@ -1,16 +1,20 @@
-use std::io;
+use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
 use clap::Parser;
 use entities::BlockData;
-use eth_archive_parquet_writer::{ParquetWriter, ParquetConfig, BlockRange};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::mpsc;
+use crate::error::Result;
 use crate::parquet::{Blocks, Extrinsics, Calls, Events};
 use crate::sqlite::SQLite;
+use crate::writer::{BlockRange, Compression, FlushedRange, PageVersion, ParquetConfig, ParquetWriter};
 
 mod entities;
 mod parquet;
 mod sqlite;
 mod error;
+mod writer;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -18,68 +22,179 @@ struct Args {
     /// An output directory for parquet files
     #[clap(short, long)]
     out_dir: String,
+
+    /// Compression codec applied to every column chunk
+    #[clap(long, value_enum, default_value_t = Compression::Zstd)]
+    compression: Compression,
+
+    /// Codec-specific compression level (e.g. a ZSTD or gzip level); codec default if unset.
+    /// Validity of the level for the chosen codec (e.g. gzip tops out at 9) is still
+    /// checked against the codec itself, not just this range.
+    #[clap(long, value_parser = clap::value_parser!(i32).range(0..=22))]
+    compression_level: Option<i32>,
+
+    /// Number of rows per row group
+    #[clap(long, default_value_t = 64, value_parser = clap::value_parser!(usize).range(1..))]
+    row_group_size: usize,
+
+    /// Parquet data page version
+    #[clap(long, value_enum, default_value_t = PageVersion::V1)]
+    page_version: PageVersion,
+
+    /// Infer native Struct/List columns for `args`/`origin`/`signature`/`error`
+    /// instead of storing them as opaque JSON-string Binary columns
+    #[clap(long)]
+    infer_json_columns: bool,
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
 
-    let (tx, _rx) = mpsc::unbounded_channel();
-    let block_config = ParquetConfig {
-        name: "block".to_string(),
-        path: PathBuf::from(&args.out_dir),
-        channel_size: 100,
-        items_per_file: 4096,
-        items_per_row_group: 64,
-    };
-    let block_writer: ParquetWriter<Blocks> = ParquetWriter::new(block_config, tx.clone());
+    if let Err(err) = run(args).await {
+        eprintln!("fatal error: {}", err);
+        std::process::exit(1);
+    }
+}
 
-    let extrinsic_config = ParquetConfig {
-        name: "extrinsic".to_string(),
-        path: PathBuf::from(&args.out_dir),
-        channel_size: 100,
-        items_per_file: 4096,
-        items_per_row_group: 64,
-    };
-    let extrinsic_writer: ParquetWriter<Extrinsics> = ParquetWriter::new(extrinsic_config, tx.clone());
+/// Dataset names passed to `ParquetConfig`; also the keys `record_flush` tracks in
+/// `last_flushed`.
+const DATASETS: [&str; 4] = ["block", "extrinsic", "call", "event"];
 
-    let call_config = ParquetConfig {
-        name: "call".to_string(),
-        path: PathBuf::from(&args.out_dir),
-        channel_size: 100,
-        items_per_file: 4096,
-        items_per_row_group: 64,
-    };
-    let call_writer: ParquetWriter<Calls> = ParquetWriter::new(call_config, tx.clone());
+/// Notes that `flushed.name` has durably written everything up to `flushed.to`,
+/// then advances the persisted checkpoint to the minimum flushed height across
+/// all datasets. Keeping the checkpoint at that minimum, instead of the height of
+/// whichever dataset just flushed, means a crash never skips rows that are still
+/// sitting unflushed in a slower dataset's writer buffer.
+fn record_flush(
+    sqlite: &SQLite,
+    last_flushed: &mut HashMap<String, i64>,
+    flushed: FlushedRange,
+) -> Result<()> {
+    let to = i64::try_from(flushed.to).unwrap();
+    last_flushed
+        .entry(flushed.name)
+        .and_modify(|height| *height = (*height).max(to))
+        .or_insert(to);
 
-    let event_config = ParquetConfig {
-        name: "event".to_string(),
+    if let Some(&min) = last_flushed.values().min() {
+        sqlite.set_checkpoint(min)?;
+    }
+
+    Ok(())
+}
+
+async fn run(args: Args) -> Result<()> {
+    crate::parquet::set_infer_json_columns(args.infer_json_columns);
+
+    // Validate the codec/level combination once up front, so a bad
+    // `--compression-level` fails immediately instead of surfacing on the first
+    // flush, deep inside a spawned writer task, after rows have already been
+    // consumed off stdin.
+    args.compression.to_options(args.compression_level)?;
+
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel::<FlushedRange>();
+
+    let make_config = |name: &str| ParquetConfig {
+        name: name.to_string(),
         path: PathBuf::from(&args.out_dir),
         channel_size: 100,
         items_per_file: 4096,
-        items_per_row_group: 64,
+        items_per_row_group: args.row_group_size,
+        compression: args.compression,
+        compression_level: args.compression_level,
+        page_version: args.page_version,
     };
-    let event_writer: ParquetWriter<Events> = ParquetWriter::new(event_config, tx);
+
+    let block_writer: ParquetWriter<Blocks> =
+        ParquetWriter::new(make_config("block"), done_tx.clone());
+    let extrinsic_writer: ParquetWriter<Extrinsics> =
+        ParquetWriter::new(make_config("extrinsic"), done_tx.clone());
+    let call_writer: ParquetWriter<Calls> = ParquetWriter::new(make_config("call"), done_tx.clone());
+    let event_writer: ParquetWriter<Events> = ParquetWriter::new(make_config("event"), done_tx.clone());
+    drop(done_tx);
 
     let sqlite_path = std::path::Path::new(&args.out_dir).join("metadata.sqlite");
-    let sqlite = SQLite::new(&sqlite_path).unwrap();
-    sqlite.init_schema().unwrap();
+    let sqlite = SQLite::new(&sqlite_path)?;
+    sqlite.init_schema()?;
+
+    // -1 on a fresh start, so block 0 is not skipped as already committed.
+    let checkpoint = sqlite.get_checkpoint()?.unwrap_or(-1);
+    if checkpoint >= 0 {
+        eprintln!("resuming after block {checkpoint}");
+    }
+
+    // Every dataset starts out "caught up" to the persisted checkpoint; each one
+    // only moves forward as its own row groups are confirmed flushed.
+    let mut last_flushed: HashMap<String, i64> = DATASETS
+        .iter()
+        .map(|name| (name.to_string(), checkpoint))
+        .collect();
+
+    let dead_letter_path = std::path::Path::new(&args.out_dir).join("dead_letter.ndjson");
+    let mut dead_letter = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&dead_letter_path)?;
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
 
     loop {
-        let mut line = String::new();
-        io::stdin().read_line(&mut line).unwrap();
-        let block_data: BlockData = serde_json::from_str(&line).unwrap();
+        let line = tokio::select! {
+            biased;
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("shutting down, draining writers...");
+                break;
+            }
+            Some(flushed) = done_rx.recv() => {
+                record_flush(&sqlite, &mut last_flushed, flushed)?;
+                continue;
+            }
+            line = lines.next_line() => line?,
+        };
+
+        let Some(line) = line else {
+            // stdin closed: drain and exit like a normal shutdown.
+            break;
+        };
+
+        let block_data: BlockData = match serde_json::from_str(&line) {
+            Ok(block_data) => block_data,
+            Err(err) => {
+                eprintln!("skipping malformed line: {}", err);
+                writeln!(dead_letter, "{}", line)?;
+                continue;
+            }
+        };
+
+        let height = i64::from(block_data.header.height);
+        if height <= checkpoint {
+            continue;
+        }
 
         let block_range = BlockRange {
             from: usize::try_from(block_data.header.height).unwrap(),
             to: usize::try_from(block_data.header.height).unwrap(),
         };
-        block_writer.send((block_range, vec![block_data.header])).await;
-        extrinsic_writer.send((block_range, block_data.extrinsics)).await;
-        call_writer.send((block_range, block_data.calls)).await;
-        event_writer.send((block_range, block_data.events)).await;
+        block_writer.send((block_range, vec![block_data.header])).await?;
+        extrinsic_writer.send((block_range, block_data.extrinsics)).await?;
+        call_writer.send((block_range, block_data.calls)).await?;
+        event_writer.send((block_range, block_data.events)).await?;
         if let Some(metadata) = &block_data.metadata {
-            sqlite.insert_metadata(metadata).unwrap();
+            sqlite.insert_metadata(metadata)?;
         }
     }
+
+    block_writer.close().await?;
+    extrinsic_writer.close().await?;
+    call_writer.close().await?;
+    event_writer.close().await?;
+
+    // Every writer task has now exited, so all of its `FlushedRange` notifications
+    // (including the final partial file) are already sitting in the channel.
+    while let Some(flushed) = done_rx.recv().await {
+        record_flush(&sqlite, &mut last_flushed, flushed)?;
+    }
+
+    Ok(())
 }
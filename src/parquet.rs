@@ -1,22 +1,249 @@
-use eth_archive_parquet_writer::{IntoRowGroups, BlockNum, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::writer::{IntoRowGroups, BlockNum};
+use crate::error::{Error, Result};
 use crate::entities::{Block, Call, Event, Extrinsic};
 use arrow2::array::{
     Array, MutableArray, MutableBinaryArray as ArrowMutableBinaryArray,
-    Int32Vec, MutableBooleanArray, Int64Vec,
+    Int32Vec, MutableBooleanArray, Int64Vec, MutableDictionaryArray, TryPush,
+    MutableFixedSizeBinaryArray,
 };
-use arrow2::datatypes::{DataType, Field, Schema};
+use arrow2::datatypes::{DataType, Field, Schema, IntegerType};
 use arrow2::chunk::Chunk as ArrowChunk;
 use arrow2::compute::sort::{sort_to_indices, SortOptions};
 use arrow2::compute::take::take as arrow_take;
+use arrow2::io::json::read as json_read;
 
 type Chunk = ArrowChunk<Box<dyn Array>>;
 
 type MutableBinaryArray = ArrowMutableBinaryArray<i64>;
 
+// Low-cardinality string columns (spec ids, event/call names, phases, ...) repeat a
+// handful of distinct values across millions of rows, so we key them into a
+// dictionary instead of storing the bytes out in full every time.
+type MutableDictBinaryArray = MutableDictionaryArray<i32, MutableBinaryArray>;
+
+fn dict_binary_field(name: &str, is_nullable: bool) -> Field {
+    Field::new(
+        name,
+        DataType::Dictionary(IntegerType::Int32, Box::new(DataType::Binary), false),
+        is_nullable,
+    )
+}
+
 fn value_to_string(value: Option<serde_json::Value>) -> Option<String> {
     value.map(|value| serde_json::to_string(&value).unwrap())
 }
 
+static INFER_JSON_COLUMNS: AtomicBool = AtomicBool::new(false);
+
+/// Turns on the opt-in nested-column mode for `args`/`origin`/`signature`/`error`
+/// for the lifetime of the process. Off by default: those fields stay JSON
+/// strings in a plain Binary column.
+pub fn set_infer_json_columns(enabled: bool) {
+    INFER_JSON_COLUMNS.store(enabled, Ordering::Relaxed);
+}
+
+fn infer_json_columns() -> bool {
+    INFER_JSON_COLUMNS.load(Ordering::Relaxed)
+}
+
+/// Backs the heterogeneous JSON columns (`args`, `origin`, `signature`, `error`).
+///
+/// By default every row is serialized to a JSON string and stored as Binary, same
+/// as before. With [`set_infer_json_columns`] turned on, we additionally buffer the
+/// raw `serde_json::Value`s, keyed by a caller-supplied group (the call/event
+/// `name`, or `""` when there's no natural grouping key). A real file mixes dozens
+/// of distinct `(pallet, call)` shapes, so inferring one type over the whole
+/// column directly almost always hits a genuine conflict between groups and falls
+/// back to Binary for everything; instead we infer each group's type on its own
+/// buffered values and union the resulting struct fields across groups, so one
+/// call's unrelated args shape can't sink another's. If every value was null, or
+/// arrow2 can't deserialize the buffered rows against the type it inferred, we
+/// fall back to the plain JSON-string column.
+#[derive(Debug, Default)]
+pub struct JsonColumn {
+    binary: MutableBinaryArray,
+    values: Vec<serde_json::Value>,
+    groups: std::collections::HashMap<String, Vec<usize>>,
+}
+
+impl JsonColumn {
+    fn push(&mut self, group: &str, value: Option<serde_json::Value>) {
+        if !infer_json_columns() {
+            self.binary.push(value_to_string(value));
+            return;
+        }
+
+        self.binary.push(value_to_string(value.clone()));
+        let index = self.values.len();
+        self.values.push(value.unwrap_or(serde_json::Value::Null));
+        self.groups.entry(group.to_string()).or_default().push(index);
+    }
+
+    fn inferred_type(&self) -> Option<DataType> {
+        if !infer_json_columns() {
+            return None;
+        }
+
+        let mut merged: Vec<Field> = Vec::new();
+        for indices in self.groups.values() {
+            let group_values: Vec<_> = indices.iter().map(|&i| self.values[i].clone()).collect();
+            if group_values.iter().all(serde_json::Value::is_null) {
+                continue;
+            }
+
+            let batch = serde_json::Value::Array(group_values);
+            let group_type = json_read::infer(&batch).ok()?;
+
+            // A single group (e.g. the ungrouped signature/error columns) is the
+            // whole column; take its type as-is rather than forcing it into a
+            // struct union of one.
+            if self.groups.len() == 1 {
+                return Some(group_type);
+            }
+
+            match group_type {
+                DataType::Struct(fields) => merge_struct_fields(&mut merged, fields),
+                // A non-object group can't be unioned into a struct column.
+                _ => return None,
+            }
+        }
+
+        if merged.is_empty() {
+            None
+        } else {
+            Some(DataType::Struct(merged))
+        }
+    }
+
+    fn field(&self, name: &str) -> Field {
+        let data_type = self
+            .inferred_type()
+            .filter(|data_type| {
+                let batch = serde_json::Value::Array(self.values.clone());
+                json_read::deserialize(&batch, data_type.clone()).is_ok()
+            })
+            .unwrap_or(DataType::Binary);
+        Field::new(name, data_type, true)
+    }
+
+    fn into_array(self, field: &Field) -> Box<dyn Array> {
+        if field.data_type != DataType::Binary {
+            let batch = serde_json::Value::Array(self.values.clone());
+            if let Ok(array) = json_read::deserialize(&batch, field.data_type.clone()) {
+                return array;
+            }
+        }
+        self.binary.as_box()
+    }
+}
+
+/// Merges one group's inferred struct fields into the running union, widening
+/// to `Binary` when two groups disagree on the type of the same field name
+/// rather than picking one arbitrarily.
+fn merge_struct_fields(into: &mut Vec<Field>, fields: Vec<Field>) {
+    for field in fields {
+        if let Some(existing) = into.iter_mut().find(|f| f.name == field.name) {
+            if existing.data_type != field.data_type {
+                existing.data_type = DataType::Binary;
+            }
+            existing.is_nullable = true;
+        } else {
+            let mut field = field;
+            field.is_nullable = true;
+            into.push(field);
+        }
+    }
+}
+
+const HASH_SIZE: usize = 32;
+
+// Malformed hex (odd length, a non-hex digit) means the value can't be decoded at
+// all, so that's a hard `Result` error. A well-formed hash of the wrong length
+// (e.g. a non-standard chain's shorter/longer hash) decodes fine; it's up to
+// `HashColumn` to fall back to a variable-length column for it instead of
+// rejecting the row.
+fn decode_hash(value: &str) -> Result<Vec<u8>> {
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    if hex.len() % 2 != 0 {
+        return Err(Error::Internal(format!(
+            "malformed hex hash \"{}\": odd number of hex digits",
+            value
+        )));
+    }
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::Internal(format!(
+            "malformed hex hash \"{}\": non-hex digit",
+            value
+        )));
+    }
+
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    for i in (0..hex.len()).step_by(2) {
+        let byte = u8::from_str_radix(&hex[i..i + 2], 16)
+            .map_err(|_| Error::Internal(format!("malformed hex hash \"{}\"", value)))?;
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
+
+/// Backs the block/extrinsic hash columns. Every row is hex-decoded and kept as
+/// `FixedSizeBinary(32)` as long as every hash seen so far was exactly 32 bytes.
+/// The moment one isn't (a non-standard chain's shorter/longer hash, say), the
+/// whole column falls back to a plain variable-length `Binary` column rather
+/// than rejecting the row that broke the assumption.
+#[derive(Debug)]
+pub struct HashColumn {
+    fixed: MutableFixedSizeBinaryArray,
+    binary: MutableBinaryArray,
+    fallback: bool,
+}
+
+impl HashColumn {
+    fn new() -> Self {
+        HashColumn {
+            fixed: MutableFixedSizeBinaryArray::new(HASH_SIZE),
+            binary: MutableBinaryArray::default(),
+            fallback: false,
+        }
+    }
+
+    fn push(&mut self, value: &str) -> Result<()> {
+        let bytes = decode_hash(value)?;
+
+        if !self.fallback {
+            if bytes.len() == HASH_SIZE {
+                let mut fixed = [0u8; HASH_SIZE];
+                fixed.copy_from_slice(&bytes);
+                self.fixed.push(Some(fixed));
+            } else {
+                self.fallback = true;
+            }
+        }
+        self.binary.push(Some(bytes));
+
+        Ok(())
+    }
+
+    fn field(&self, name: &str, is_nullable: bool) -> Field {
+        let data_type = if self.fallback {
+            DataType::Binary
+        } else {
+            DataType::FixedSizeBinary(HASH_SIZE)
+        };
+        Field::new(name, data_type, is_nullable)
+    }
+
+    fn into_array(self) -> Box<dyn Array> {
+        if self.fallback {
+            self.binary.as_box()
+        } else {
+            self.fixed.as_box()
+        }
+    }
+}
+
 fn extract_block_num(block_id: &String) -> i32 {
     block_id.split('-').next().unwrap().parse::<i32>().unwrap()
 }
@@ -45,34 +272,51 @@ impl BlockNum for Extrinsic {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Blocks {
     pub id: MutableBinaryArray,
     pub height: Int32Vec,
-    pub hash: MutableBinaryArray,
-    pub parent_hash: MutableBinaryArray,
-    pub state_root: MutableBinaryArray,
-    pub extrinsics_root: MutableBinaryArray,
+    pub hash: HashColumn,
+    pub parent_hash: HashColumn,
+    pub state_root: HashColumn,
+    pub extrinsics_root: HashColumn,
     pub timestamp: MutableBinaryArray,
-    pub spec_id: MutableBinaryArray,
-    pub validator: MutableBinaryArray,
+    pub spec_id: MutableDictBinaryArray,
+    pub validator: MutableDictBinaryArray,
     pub len: usize,
 }
 
+impl Default for Blocks {
+    fn default() -> Self {
+        Blocks {
+            id: MutableBinaryArray::default(),
+            height: Int32Vec::default(),
+            hash: HashColumn::new(),
+            parent_hash: HashColumn::new(),
+            state_root: HashColumn::new(),
+            extrinsics_root: HashColumn::new(),
+            timestamp: MutableBinaryArray::default(),
+            spec_id: MutableDictBinaryArray::default(),
+            validator: MutableDictBinaryArray::default(),
+            len: 0,
+        }
+    }
+}
+
 impl IntoRowGroups for Blocks {
     type Elem = Block;
 
-    fn schema() -> Schema {
+    fn schema(&self) -> Schema {
         Schema::from(vec![
             Field::new("id", DataType::Binary, false),
             Field::new("height", DataType::Int32, false),
-            Field::new("hash", DataType::Binary, false),
-            Field::new("parent_hash", DataType::Binary, false),
-            Field::new("state_root", DataType::Binary, false),
-            Field::new("extrinsics_root", DataType::Binary, false),
+            self.hash.field("hash", false),
+            self.parent_hash.field("parent_hash", false),
+            self.state_root.field("state_root", false),
+            self.extrinsics_root.field("extrinsics_root", false),
             Field::new("timestamp", DataType::Binary, false),
-            Field::new("spec_id", DataType::Binary, false),
-            Field::new("validator", DataType::Binary, true),
+            dict_binary_field("spec_id", false),
+            dict_binary_field("validator", true),
         ])
     }
 
@@ -89,13 +333,18 @@ impl IntoRowGroups for Blocks {
         )
         .unwrap();
 
+        let hash = self.hash.into_array();
+        let parent_hash = self.parent_hash.into_array();
+        let state_root = self.state_root.into_array();
+        let extrinsics_root = self.extrinsics_root.into_array();
+
         Chunk::new(vec![
             arrow_take(self.id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(height.as_ref(), &indices).unwrap(),
-            arrow_take(self.hash.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.parent_hash.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.state_root.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.extrinsics_root.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(hash.as_ref(), &indices).unwrap(),
+            arrow_take(parent_hash.as_ref(), &indices).unwrap(),
+            arrow_take(state_root.as_ref(), &indices).unwrap(),
+            arrow_take(extrinsics_root.as_ref(), &indices).unwrap(),
             arrow_take(self.timestamp.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.spec_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.validator.as_box().as_ref(), &indices).unwrap(),
@@ -103,15 +352,21 @@ impl IntoRowGroups for Blocks {
     }
 
     fn push(&mut self, elem: Self::Elem) -> Result<()> {
+        // Decode every hash before touching any column, so a malformed one is
+        // rejected without leaving a partial row behind.
+        for hash in [&elem.hash, &elem.parent_hash, &elem.state_root, &elem.extrinsics_root] {
+            decode_hash(hash)?;
+        }
+
         self.id.push(Some(elem.id));
         self.height.push(Some(elem.height));
-        self.hash.push(Some(elem.hash));
-        self.parent_hash.push(Some(elem.parent_hash));
-        self.state_root.push(Some(elem.state_root));
-        self.extrinsics_root.push(Some(elem.extrinsics_root));
+        self.hash.push(&elem.hash)?;
+        self.parent_hash.push(&elem.parent_hash)?;
+        self.state_root.push(&elem.state_root)?;
+        self.extrinsics_root.push(&elem.extrinsics_root)?;
         self.timestamp.push(Some(elem.timestamp));
-        self.spec_id.push(Some(elem.spec_id));
-        self.validator.push(elem.validator);
+        self.spec_id.try_push(Some(elem.spec_id))?;
+        self.validator.try_push(elem.validator)?;
 
         self.len += 1;
 
@@ -123,39 +378,61 @@ impl IntoRowGroups for Blocks {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Extrinsics {
     pub id: MutableBinaryArray,
     pub block_id: MutableBinaryArray,
     pub index_in_block: Int32Vec,
+    // Low-cardinality but already a fixed-width Int32, so there's no binary
+    // payload here for a dictionary to deduplicate.
     pub version: Int32Vec,
-    pub signature: MutableBinaryArray,
+    pub signature: JsonColumn,
     pub call_id: MutableBinaryArray,
     pub fee: Int64Vec,
     pub tip: Int64Vec,
     pub success: MutableBooleanArray,
-    pub error: MutableBinaryArray,
-    pub hash: MutableBinaryArray,
+    pub error: JsonColumn,
+    pub hash: HashColumn,
     pub pos: Int32Vec,
     pub len: usize,
 }
 
+impl Default for Extrinsics {
+    fn default() -> Self {
+        Extrinsics {
+            id: MutableBinaryArray::default(),
+            block_id: MutableBinaryArray::default(),
+            index_in_block: Int32Vec::default(),
+            version: Int32Vec::default(),
+            signature: JsonColumn::default(),
+            call_id: MutableBinaryArray::default(),
+            fee: Int64Vec::default(),
+            tip: Int64Vec::default(),
+            success: MutableBooleanArray::default(),
+            error: JsonColumn::default(),
+            hash: HashColumn::new(),
+            pos: Int32Vec::default(),
+            len: 0,
+        }
+    }
+}
+
 impl IntoRowGroups for Extrinsics {
     type Elem = Extrinsic;
 
-    fn schema() -> Schema {
+    fn schema(&self) -> Schema {
         Schema::from(vec![
             Field::new("id", DataType::Binary, false),
             Field::new("block_id", DataType::Binary, false),
             Field::new("index_in_block", DataType::Int32, false),
             Field::new("version", DataType::Int32, false),
-            Field::new("signature", DataType::Binary, true),
+            self.signature.field("signature"),
             Field::new("call_id", DataType::Binary, false),
             Field::new("fee", DataType::Int64, true),
-            Field::new("fee", DataType::Int64, true),
+            Field::new("tip", DataType::Int64, true),
             Field::new("success", DataType::Boolean, false),
-            Field::new("error", DataType::Binary, true),
-            Field::new("hash", DataType::Binary, false),
+            self.error.field("error"),
+            self.hash.field("hash", false),
             Field::new("pos", DataType::Int32, false),
         ])
     }
@@ -173,34 +450,43 @@ impl IntoRowGroups for Extrinsics {
         )
         .unwrap();
 
+        let schema = self.schema();
+        let signature = self.signature.into_array(&schema.fields[4]);
+        let error = self.error.into_array(&schema.fields[9]);
+        let hash = self.hash.into_array();
+
         Chunk::new(vec![
             arrow_take(id.as_ref(), &indices).unwrap(),
             arrow_take(self.block_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.index_in_block.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.version.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.signature.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(signature.as_ref(), &indices).unwrap(),
             arrow_take(self.call_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.fee.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.tip.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.success.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.error.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.hash.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(error.as_ref(), &indices).unwrap(),
+            arrow_take(hash.as_ref(), &indices).unwrap(),
             arrow_take(self.pos.as_box().as_ref(), &indices).unwrap(),
         ])
     }
 
     fn push(&mut self, elem: Self::Elem) -> Result<()> {
+        // Decode the hash before touching any column, so a malformed one is
+        // rejected without leaving a partial row behind.
+        decode_hash(&elem.hash)?;
+
         self.id.push(Some(elem.id));
         self.block_id.push(Some(elem.block_id));
         self.index_in_block.push(Some(elem.index_in_block));
         self.version.push(Some(elem.version));
-        self.signature.push(value_to_string(elem.signature));
+        self.signature.push("", elem.signature);
         self.call_id.push(Some(elem.call_id));
         self.fee.push(elem.fee);
         self.tip.push(elem.tip);
         self.success.push(Some(elem.success));
-        self.error.push(value_to_string(elem.error));
-        self.hash.push(Some(elem.hash));
+        self.error.push("", elem.error);
+        self.hash.push(&elem.hash)?;
         self.pos.push(Some(elem.pos));
 
         self.len += 1;
@@ -219,11 +505,11 @@ pub struct Calls {
     pub parent_id: MutableBinaryArray,
     pub block_id: MutableBinaryArray,
     pub extrinsic_id: MutableBinaryArray,
-    pub origin: MutableBinaryArray,
+    pub origin: JsonColumn,
     pub success: MutableBooleanArray,
-    pub error: MutableBinaryArray,
-    pub name: MutableBinaryArray,
-    pub args: MutableBinaryArray,
+    pub error: JsonColumn,
+    pub name: MutableDictBinaryArray,
+    pub args: JsonColumn,
     pub pos: Int32Vec,
     pub len: usize,
 }
@@ -231,17 +517,17 @@ pub struct Calls {
 impl IntoRowGroups for Calls {
     type Elem = Call;
 
-    fn schema() -> Schema {
+    fn schema(&self) -> Schema {
         Schema::from(vec![
             Field::new("id", DataType::Binary, false),
             Field::new("parent_id", DataType::Binary, true),
             Field::new("block_id", DataType::Binary, false),
             Field::new("extrinsic_id", DataType::Binary, false),
-            Field::new("origin", DataType::Binary, true),
+            self.origin.field("origin"),
             Field::new("success", DataType::Boolean, false),
-            Field::new("error", DataType::Binary, true),
-            Field::new("name", DataType::Binary, false),
-            Field::new("args", DataType::Binary, true),
+            self.error.field("error"),
+            dict_binary_field("name", false),
+            self.args.field("args"),
             Field::new("pos", DataType::Int32, false),
         ])
     }
@@ -259,16 +545,21 @@ impl IntoRowGroups for Calls {
         )
         .unwrap();
 
+        let schema = self.schema();
+        let origin = self.origin.into_array(&schema.fields[4]);
+        let error = self.error.into_array(&schema.fields[6]);
+        let args = self.args.into_array(&schema.fields[8]);
+
         Chunk::new(vec![
             arrow_take(id.as_ref(), &indices).unwrap(),
             arrow_take(self.parent_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.block_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.extrinsic_id.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.origin.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(origin.as_ref(), &indices).unwrap(),
             arrow_take(self.success.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.error.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(error.as_ref(), &indices).unwrap(),
             arrow_take(self.name.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.args.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(args.as_ref(), &indices).unwrap(),
             arrow_take(self.pos.as_box().as_ref(), &indices).unwrap(),
         ])
     }
@@ -278,11 +569,11 @@ impl IntoRowGroups for Calls {
         self.parent_id.push(elem.parent_id);
         self.block_id.push(Some(elem.block_id));
         self.extrinsic_id.push(Some(elem.extrinsic_id));
-        self.origin.push(value_to_string(elem.origin));
+        self.origin.push(&elem.name, elem.origin);
         self.success.push(Some(elem.success));
-        self.error.push(value_to_string(elem.error));
-        self.name.push(Some(elem.name));
-        self.args.push(value_to_string(elem.args));
+        self.error.push(&elem.name, elem.error);
+        self.args.push(&elem.name, elem.args);
+        self.name.try_push(Some(elem.name))?;
         self.pos.push(Some(elem.pos));
 
         self.len += 1;
@@ -300,11 +591,11 @@ pub struct Events {
     pub id: MutableBinaryArray,
     pub block_id: MutableBinaryArray,
     pub index_in_block: Int32Vec,
-    pub phase: MutableBinaryArray,
+    pub phase: MutableDictBinaryArray,
     pub extrinsic_id: MutableBinaryArray,
     pub call_id: MutableBinaryArray,
-    pub name: MutableBinaryArray,
-    pub args: MutableBinaryArray,
+    pub name: MutableDictBinaryArray,
+    pub args: JsonColumn,
     pub pos: Int32Vec,
     pub len: usize,
 }
@@ -312,16 +603,16 @@ pub struct Events {
 impl IntoRowGroups for Events {
     type Elem = Event;
 
-    fn schema() -> Schema {
+    fn schema(&self) -> Schema {
         Schema::from(vec![
             Field::new("id", DataType::Binary, false),
             Field::new("block_id", DataType::Binary, false),
             Field::new("index_in_block", DataType::Int32, false),
-            Field::new("phase", DataType::Binary, false),
+            dict_binary_field("phase", false),
             Field::new("extrinsic_id", DataType::Binary, true),
             Field::new("call_id", DataType::Binary, true),
-            Field::new("name", DataType::Binary, false),
-            Field::new("args", DataType::Binary, true),
+            dict_binary_field("name", false),
+            self.args.field("args"),
             Field::new("pos", DataType::Int32, false),
         ])
     }
@@ -339,6 +630,9 @@ impl IntoRowGroups for Events {
         )
         .unwrap();
 
+        let schema = self.schema();
+        let args = self.args.into_array(&schema.fields[7]);
+
         Chunk::new(vec![
             arrow_take(id.as_ref(), &indices).unwrap(),
             arrow_take(self.block_id.as_box().as_ref(), &indices).unwrap(),
@@ -347,7 +641,7 @@ impl IntoRowGroups for Events {
             arrow_take(self.extrinsic_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.call_id.as_box().as_ref(), &indices).unwrap(),
             arrow_take(self.name.as_box().as_ref(), &indices).unwrap(),
-            arrow_take(self.args.as_box().as_ref(), &indices).unwrap(),
+            arrow_take(args.as_ref(), &indices).unwrap(),
             arrow_take(self.pos.as_box().as_ref(), &indices).unwrap(),
         ])
     }
@@ -356,11 +650,11 @@ impl IntoRowGroups for Events {
         self.id.push(Some(elem.id));
         self.block_id.push(Some(elem.block_id));
         self.index_in_block.push(Some(elem.index_in_block));
-        self.phase.push(Some(elem.phase));
+        self.phase.try_push(Some(elem.phase))?;
         self.extrinsic_id.push(elem.extrinsic_id);
         self.call_id.push(elem.call_id);
-        self.name.push(Some(elem.name));
-        self.args.push(value_to_string(elem.args));
+        self.args.push(&elem.name, elem.args);
+        self.name.try_push(Some(elem.name))?;
         self.pos.push(Some(elem.pos));
 
         self.len += 1;
@@ -1,5 +1,5 @@
 use crate::entities::Metadata;
-use rusqlite::{Connection, Result};
+use rusqlite::{Connection, OptionalExtension};
 use crate::error::Error;
 use std::path::Path;
 
@@ -16,7 +16,7 @@ impl SQLite {
 
     pub fn init_schema(&self) -> Result<(), Error> {
         self.connection.execute(
-            "CREATE TABLE metadata (
+            "CREATE TABLE IF NOT EXISTS metadata (
                 id varchar primary key,
                 spec_name varchar not null,
                 spec_version integer,
@@ -26,12 +26,23 @@ impl SQLite {
             );",
             []
         )?;
+        self.connection.execute(
+            "CREATE TABLE IF NOT EXISTS progress (
+                id integer primary key,
+                block_height integer not null
+            );",
+            []
+        )?;
         Ok(())
     }
 
+    /// Idempotent: a crash can replay the same line before its checkpoint
+    /// advances, so a repeat insert for an already-seen `id` is a no-op rather
+    /// than a primary-key violation.
     pub fn insert_metadata(&self, metadata: &Metadata) -> Result<(), Error> {
         self.connection.execute(
-            "INSERT INTO metadata VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO metadata VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO NOTHING",
             (
                 &metadata.id,
                 &metadata.spec_name,
@@ -43,4 +54,26 @@ impl SQLite {
         )?;
         Ok(())
     }
+
+    /// Highest block height fully committed in a previous run, or `None` on a fresh start.
+    pub fn get_checkpoint(&self) -> Result<Option<i64>, Error> {
+        let height = self
+            .connection
+            .query_row(
+                "SELECT block_height FROM progress WHERE id = 0",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(height)
+    }
+
+    pub fn set_checkpoint(&self, block_height: i64) -> Result<(), Error> {
+        self.connection.execute(
+            "INSERT INTO progress (id, block_height) VALUES (0, ?1)
+             ON CONFLICT(id) DO UPDATE SET block_height = excluded.block_height",
+            [block_height],
+        )?;
+        Ok(())
+    }
 }
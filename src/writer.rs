@@ -0,0 +1,283 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use arrow2::array::Array;
+use arrow2::chunk::Chunk;
+use arrow2::datatypes::Schema;
+use arrow2::io::parquet::write::{
+    CompressionOptions, Encoding, FileWriter, GzipLevel, RowGroupIterator, Version,
+    WriteOptions, ZstdLevel,
+};
+use clap::ValueEnum;
+use tokio::sync::mpsc;
+
+use crate::error::{Error, Result};
+
+/// A row's position within the chain, used to name output files after the
+/// block range they cover.
+pub trait BlockNum {
+    fn block_num(&self) -> i64;
+}
+
+/// Implemented by the per-dataset row buffers in `parquet.rs` so the writer
+/// can flush them to disk without knowing anything about their columns.
+///
+/// `schema` takes `&self` rather than being a bare associated function because
+/// some columns (the JSON-inferred ones, in opt-in mode) only know their real
+/// Arrow type once every buffered row has been seen.
+pub trait IntoRowGroups: Default + Send + 'static {
+    type Elem: BlockNum + Send;
+
+    fn schema(&self) -> Schema;
+    fn into_chunk(self) -> Chunk<Box<dyn Array>>;
+    fn push(&mut self, elem: Self::Elem) -> Result<()>;
+    fn len(&self) -> usize;
+
+    /// Per-column encodings, in schema order. Defaults to `PLAIN` everywhere;
+    /// override for columns that benefit from a specialized encoding (e.g.
+    /// `DELTA_BINARY_PACKED` for a monotonic Int32 column).
+    fn encodings(&self) -> Vec<Vec<Encoding>> {
+        self.schema()
+            .fields
+            .iter()
+            .map(|_| vec![Encoding::Plain])
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BlockRange {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Reported back to the caller once a file for `name` has actually landed on
+/// disk as `path`, covering every row up to and including block `to`. Until this
+/// fires, rows sent via [`ParquetWriter::send`] only live in the writer's mpsc
+/// channel and in-memory buffer, and are lost on a crash.
+#[derive(Debug, Clone)]
+pub struct FlushedRange {
+    pub name: String,
+    pub path: PathBuf,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    Zstd,
+    Snappy,
+    Gzip,
+    Lz4,
+    None,
+}
+
+impl Compression {
+    /// Builds the codec options for this compression, validating `level` against
+    /// the specific codec (e.g. gzip tops out at 9, zstd at 22). Callers should
+    /// call this once up front to fail fast on a bad `--compression-level`
+    /// instead of discovering it deep inside a writer task on the first flush.
+    pub(crate) fn to_options(self, level: Option<i32>) -> Result<CompressionOptions> {
+        let options = match self {
+            Compression::Zstd => {
+                let level = level
+                    .map(|level| {
+                        ZstdLevel::try_new(level).map_err(|err| {
+                            Error::Internal(format!("invalid zstd compression level {level}: {err}"))
+                        })
+                    })
+                    .transpose()?;
+                CompressionOptions::Zstd(level)
+            }
+            Compression::Snappy => CompressionOptions::Snappy,
+            Compression::Gzip => {
+                let level = level
+                    .map(|level| {
+                        let level = u8::try_from(level).map_err(|_| {
+                            Error::Internal(format!(
+                                "invalid gzip compression level {level}: must be 0-255"
+                            ))
+                        })?;
+                        GzipLevel::try_new(level).map_err(|err| {
+                            Error::Internal(format!(
+                                "invalid gzip compression level {level}: {err}"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                CompressionOptions::Gzip(level)
+            }
+            Compression::Lz4 => CompressionOptions::Lz4Raw,
+            Compression::None => CompressionOptions::Uncompressed,
+        };
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PageVersion {
+    V1,
+    V2,
+}
+
+impl From<PageVersion> for Version {
+    fn from(page_version: PageVersion) -> Self {
+        match page_version {
+            PageVersion::V1 => Version::V1,
+            PageVersion::V2 => Version::V2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParquetConfig {
+    pub name: String,
+    pub path: PathBuf,
+    pub channel_size: usize,
+    pub items_per_file: usize,
+    pub items_per_row_group: usize,
+    pub compression: Compression,
+    pub compression_level: Option<i32>,
+    pub page_version: PageVersion,
+}
+
+pub struct ParquetWriter<T: IntoRowGroups> {
+    name: String,
+    tx: mpsc::Sender<(BlockRange, Vec<T::Elem>)>,
+    handle: tokio::task::JoinHandle<Result<()>>,
+}
+
+impl<T: IntoRowGroups> ParquetWriter<T> {
+    pub fn new(config: ParquetConfig, done_tx: mpsc::UnboundedSender<FlushedRange>) -> Self {
+        let name = config.name.clone();
+        let (tx, mut rx) = mpsc::channel::<(BlockRange, Vec<T::Elem>)>(config.channel_size);
+
+        let handle = tokio::spawn(async move {
+            let mut buf = T::default();
+            let mut from = None;
+            let mut to = 0;
+
+            while let Some((range, elems)) = rx.recv().await {
+                from.get_or_insert(range.from);
+                to = range.to;
+
+                for elem in elems {
+                    if let Err(err) = buf.push(elem) {
+                        eprintln!("failed to buffer row for {}: {}", config.name, err);
+                    }
+                }
+
+                if buf.len() >= config.items_per_file {
+                    let full = std::mem::take(&mut buf);
+                    let path = flush(&config, full, from.take().unwrap_or(to), to)?;
+                    let _ = done_tx.send(FlushedRange {
+                        name: config.name.clone(),
+                        path,
+                        to,
+                    });
+                }
+            }
+
+            if buf.len() > 0 {
+                let path = flush(&config, buf, from.unwrap_or(to), to)?;
+                let _ = done_tx.send(FlushedRange {
+                    name: config.name.clone(),
+                    path,
+                    to,
+                });
+            }
+
+            Ok(())
+        });
+
+        ParquetWriter { name, tx, handle }
+    }
+
+    /// Sends a batch to the writer task. `Err` means the task has already
+    /// stopped — almost certainly because a previous write failed fatally — so
+    /// the rows in `item` were never buffered and are gone; the caller must
+    /// treat this as fatal too rather than silently dropping the batch.
+    pub async fn send(&self, item: (BlockRange, Vec<T::Elem>)) -> Result<()> {
+        self.tx
+            .send(item)
+            .await
+            .map_err(|_| Error::Internal(format!("{} parquet writer task has exited", self.name)))
+    }
+
+    /// Stops accepting new rows, waits for the buffered ones to be flushed to
+    /// disk, and surfaces any error the writer task hit while doing so.
+    pub async fn close(self) -> Result<()> {
+        drop(self.tx);
+        match self.handle.await {
+            Ok(result) => result,
+            Err(err) => Err(Error::Internal(format!(
+                "parquet writer task panicked: {err}"
+            ))),
+        }
+    }
+}
+
+/// Writes the buffered rows to one file, wrapping any failure with dataset
+/// context. A failure here means those rows never reach disk, so the caller
+/// must treat it as fatal rather than log-and-continue.
+fn flush<T: IntoRowGroups>(config: &ParquetConfig, buf: T, from: usize, to: usize) -> Result<PathBuf> {
+    write_file(config, buf, from, to)
+        .map_err(|err| Error::Internal(format!("failed to write {} parquet file: {}", config.name, err)))
+}
+
+fn write_file<T: IntoRowGroups>(
+    config: &ParquetConfig,
+    buf: T,
+    from: usize,
+    to: usize,
+) -> Result<PathBuf> {
+    let schema = buf.schema();
+    let encodings = buf.encodings();
+
+    let options = WriteOptions {
+        write_statistics: true,
+        compression: config.compression.to_options(config.compression_level)?,
+        version: config.page_version.into(),
+    };
+
+    let row_groups = into_row_groups(buf.into_chunk(), config.items_per_row_group);
+    let row_groups = RowGroupIterator::try_new(
+        row_groups.into_iter().map(Ok),
+        &schema,
+        options,
+        encodings,
+    )?;
+
+    let path = config.path.join(format!("{}_{}_{}.parquet", config.name, from, to));
+    let file = BufWriter::new(File::create(&path)?);
+
+    let mut writer = FileWriter::try_new(file, schema, options)?;
+    for row_group in row_groups {
+        writer.write(row_group?)?;
+    }
+    writer.end(None)?;
+
+    Ok(path)
+}
+
+fn into_row_groups(chunk: Chunk<Box<dyn Array>>, row_group_size: usize) -> Vec<Chunk<Box<dyn Array>>> {
+    let len = chunk.len();
+    if len == 0 {
+        return vec![chunk];
+    }
+
+    let mut row_groups = Vec::with_capacity((len + row_group_size - 1) / row_group_size);
+    let mut offset = 0;
+    while offset < len {
+        let size = row_group_size.min(len - offset);
+        let arrays = chunk
+            .arrays()
+            .iter()
+            .map(|array| array.slice(offset, size))
+            .collect::<Vec<_>>();
+        row_groups.push(Chunk::new(arrays));
+        offset += size;
+    }
+
+    row_groups
+}